@@ -0,0 +1,25 @@
+//! Generates typed AST wrapper structs from the `node-types.json` that
+//! `tree_sitter_miac` ships alongside its grammar. See `build/codegen.rs`
+//! for the generator itself; this file just wires it into Cargo.
+
+#[path = "build/codegen.rs"]
+mod codegen;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=build/codegen.rs");
+
+    let node_types: Vec<codegen::NodeType> = serde_json::from_str(tree_sitter_miac::NODE_TYPES)
+        .expect("failed to parse tree_sitter_miac::NODE_TYPES as node-types.json");
+
+    let generated = codegen::generate(&node_types);
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    let dest = Path::new(&out_dir).join("ast_nodes.rs");
+    fs::write(&dest, generated)
+        .unwrap_or_else(|e| panic!("failed to write generated AST bindings to {dest:?}: {e}"));
+}
@@ -0,0 +1,166 @@
+//! Code generator that turns a tree-sitter `node-types.json` description into
+//! typed Rust wrappers around `tree_sitter::Node`.
+//!
+//! This runs from `build.rs`, so everything here is plain, dependency-light
+//! Rust: no access to the crate being built, only `serde` and `std`.
+
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+#[derive(Debug, Deserialize)]
+pub struct NodeType {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub named: bool,
+    #[serde(default)]
+    pub fields: std::collections::BTreeMap<String, FieldInfo>,
+    #[serde(default)]
+    pub children: Option<FieldInfo>,
+}
+
+/// Only the cardinality matters for codegen: a `multiple` field becomes an
+/// iterator, a single one an `Option<Node>` (kept optional even when the
+/// grammar marks it `required`, since an `ERROR`/`MISSING` recovery node can
+/// still end up missing a field the happy-path grammar always fills in).
+#[derive(Debug, Deserialize)]
+pub struct FieldInfo {
+    pub multiple: bool,
+}
+
+/// Turns a tree-sitter node kind (e.g. `function_definition`) into a
+/// Rust enum variant / type name (e.g. `FunctionDefinition`).
+fn pascal_case(kind: &str) -> String {
+    kind.split(['_', '-'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Field names like `type` collide with Rust keywords; fall back to a raw
+/// identifier so the generated accessor still compiles. `self` and `super`
+/// cannot be raw identifiers, so they get a trailing underscore instead.
+fn escape_keyword(name: &str) -> String {
+    match name {
+        "self" | "super" => format!("{name}_"),
+        "type" | "fn" | "let" | "match" | "move" | "ref" | "where" | "while"
+        | "if" | "else" | "loop" | "return" | "struct" | "enum" | "impl" | "trait" | "use" => {
+            format!("r#{name}")
+        }
+        _ => name.to_owned(),
+    }
+}
+
+/// Generate the `SyntaxKind` enum (one variant per named node type) plus one
+/// newtype wrapper per named node type that has `fields` or `children`, each
+/// exposing typed accessors for its `fields`.
+pub fn generate(node_types: &[NodeType]) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "// @generated by build.rs from node-types.json. Do not edit by hand.").unwrap();
+    writeln!(out).unwrap();
+
+    let named: Vec<&NodeType> = node_types
+        .iter()
+        .filter(|n| n.named && (!n.fields.is_empty() || n.children.is_some()))
+        .collect();
+
+    let mut variants: BTreeSet<String> = BTreeSet::new();
+    for n in node_types.iter().filter(|n| n.named) {
+        variants.insert(pascal_case(&n.kind));
+    }
+
+    writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]").unwrap();
+    writeln!(out, "pub enum SyntaxKind {{").unwrap();
+    for variant in &variants {
+        writeln!(out, "    {variant},").unwrap();
+    }
+    writeln!(out, "    Other,").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(out, "impl SyntaxKind {{").unwrap();
+    writeln!(out, "    pub fn from_kind(kind: &str) -> SyntaxKind {{").unwrap();
+    writeln!(out, "        match kind {{").unwrap();
+    for n in node_types.iter().filter(|n| n.named) {
+        writeln!(
+            out,
+            "            {:?} => SyntaxKind::{},",
+            n.kind,
+            pascal_case(&n.kind)
+        )
+        .unwrap();
+    }
+    writeln!(out, "            _ => SyntaxKind::Other,").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+
+    for n in named {
+        let ty = pascal_case(&n.kind);
+
+        writeln!(out, "#[derive(Debug, Clone, Copy)]").unwrap();
+        writeln!(out, "pub struct {ty}<'tree>(pub tree_sitter::Node<'tree>);").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "impl<'tree> {ty}<'tree> {{").unwrap();
+        writeln!(
+            out,
+            "    pub fn cast(node: tree_sitter::Node<'tree>) -> Option<Self> {{"
+        )
+        .unwrap();
+        writeln!(out, "        if node.kind() == {:?} {{", n.kind).unwrap();
+        writeln!(out, "            Some({ty}(node))").unwrap();
+        writeln!(out, "        }} else {{").unwrap();
+        writeln!(out, "            None").unwrap();
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out).unwrap();
+
+        for (field_name, info) in &n.fields {
+            let method = escape_keyword(&field_name.replace('-', "_"));
+            if info.multiple {
+                writeln!(
+                    out,
+                    "    pub fn {method}(&self) -> impl Iterator<Item = tree_sitter::Node<'tree>> + 'tree {{"
+                )
+                .unwrap();
+                writeln!(
+                    out,
+                    "        let mut cursor = self.0.walk();"
+                )
+                .unwrap();
+                writeln!(
+                    out,
+                    "        self.0.children_by_field_name({:?}, &mut cursor).collect::<Vec<_>>().into_iter()",
+                    field_name
+                )
+                .unwrap();
+                writeln!(out, "    }}").unwrap();
+            } else {
+                writeln!(
+                    out,
+                    "    pub fn {method}(&self) -> Option<tree_sitter::Node<'tree>> {{"
+                )
+                .unwrap();
+                writeln!(
+                    out,
+                    "        self.0.child_by_field_name({field_name:?})"
+                )
+                .unwrap();
+                writeln!(out, "    }}").unwrap();
+            }
+        }
+
+        writeln!(out, "}}").unwrap();
+        writeln!(out).unwrap();
+    }
+
+    out
+}
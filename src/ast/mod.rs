@@ -0,0 +1,25 @@
+//! Typed wrappers over `tree_sitter::Node`, generated at build time from
+//! `tree_sitter_miac`'s `node-types.json` (see `build.rs`).
+//!
+//! Instead of reaching into the tree with stringly-typed
+//! `node.child_by_field_name("name").unwrap()`, callers can cast a node to
+//! its typed wrapper (e.g. `FunctionDefinition::cast`) and call field
+//! accessors whose return type matches the field's cardinality in the
+//! grammar: `Option` for a single field, an iterator for a repeated one.
+
+include!(concat!(env!("OUT_DIR"), "/ast_nodes.rs"));
+
+/// A convenience iterator adaptor mirroring [`Parameter`]'s shape, used by
+/// callers that want to walk a `parameters` node's typed children directly.
+pub struct Parameters<'tree>(pub tree_sitter::Node<'tree>);
+
+impl<'tree> Parameters<'tree> {
+    pub fn iter(&self) -> impl Iterator<Item = Parameter<'tree>> + 'tree {
+        let mut cursor = self.0.walk();
+        self.0
+            .children(&mut cursor)
+            .filter_map(Parameter::cast)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
@@ -0,0 +1,80 @@
+//! The original target: straight C. This is `translate_to_c`'s old
+//! statement formatting, moved behind the [`super::Backend`] trait, plus a
+//! `miac_concat` prelude helper for string `+`.
+
+use tree_sitter::Node;
+
+use super::Backend;
+use crate::expression::translate_expression;
+use crate::typecheck::TypeInfo;
+
+pub struct CBackend;
+
+impl Backend for CBackend {
+    fn translate_type(&self, miac_type: &str) -> Option<&'static str> {
+        match miac_type {
+            "i32" => Some("int"),
+            "f32" => Some("float"),
+            "string" => Some("char *"),
+            "bool" => Some("int"),
+            _ => None,
+        }
+    }
+
+    fn emit_prelude(&self) -> String {
+        // `strdup` alone doesn't leave room for a second operand, so the
+        // string `+` operator routes through this instead of hand-rolling
+        // `malloc`/`strcpy`/`strcat` at every call site; the caller owns the
+        // returned buffer.
+        concat!(
+            "static char *miac_concat(const char *lhs, const char *rhs) {\n",
+            "    char *buf = malloc(strlen(lhs) + strlen(rhs) + 1);\n",
+            "    strcpy(buf, lhs);\n",
+            "    strcat(buf, rhs);\n",
+            "    return buf;\n",
+            "}\n",
+        )
+        .to_owned()
+    }
+
+    fn emit_function(
+        &self,
+        name: &str,
+        return_type: &str,
+        params: &[(String, String)],
+        body: &str,
+    ) -> String {
+        let param_list = params
+            .iter()
+            .map(|(ty, name)| format!("{ty} {name}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("{return_type} {name}({param_list}) {{\n{body}}}\n")
+    }
+
+    fn emit_variable(&self, is_const: bool, ty: &str, name: &str, value: &str) -> String {
+        let mutability = if is_const { "const " } else { "" };
+        format!("{mutability}{ty} {name} = {value};\n")
+    }
+
+    fn emit_assignment(&self, name: &str, value: &str) -> String {
+        format!("{name} = {value};\n")
+    }
+
+    fn emit_while(&self, condition: &str, body: &str) -> String {
+        format!("while ({condition}) {{\n{body}}}\n")
+    }
+
+    fn emit_if(&self, condition: &str, body: &str) -> String {
+        format!("if ({condition}) {{\n{body}}}\n")
+    }
+
+    fn emit_return(&self, value: &str) -> String {
+        format!("return {value};\n")
+    }
+
+    fn emit_expression(&self, src: &str, node: &Node, types: &TypeInfo) -> String {
+        translate_expression(src, node, types)
+    }
+}
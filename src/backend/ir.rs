@@ -0,0 +1,178 @@
+//! A second, non-C target: a minimal functional/rewrite-rule IR in the
+//! style of interaction-net languages. Functions become top-level rewrite
+//! rules, `while` lowers to a self-recursive rule definition instead of a
+//! loop, `if` lowers to an inline `match` on the condition (not to a rule
+//! definition), and variable declarations become `let` bindings.
+
+use std::cell::Cell;
+
+use tree_sitter::Node;
+
+use super::Backend;
+use crate::ast::CallExpression;
+use crate::typecheck::{Type, TypeInfo};
+
+#[derive(Default)]
+pub struct IrBackend {
+    /// Generates unique names for the auxiliary rules `while` lowers to.
+    rule_counter: Cell<u32>,
+}
+
+impl IrBackend {
+    fn next_loop_rule(&self) -> String {
+        let id = self.rule_counter.get();
+        self.rule_counter.set(id + 1);
+        format!("loop${id}")
+    }
+}
+
+impl Backend for IrBackend {
+    fn translate_type(&self, miac_type: &str) -> Option<&'static str> {
+        match miac_type {
+            "i32" => Some("Int"),
+            "f32" => Some("Float"),
+            "string" => Some("Str"),
+            "bool" => Some("Bool"),
+            _ => None,
+        }
+    }
+
+    fn emit_function(
+        &self,
+        name: &str,
+        return_type: &str,
+        params: &[(String, String)],
+        body: &str,
+    ) -> String {
+        let param_list = params
+            .iter()
+            .map(|(ty, name)| format!("{name}: {ty}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("rule {name}({param_list}) -> {return_type} =\n{body}\n")
+    }
+
+    fn emit_variable(&self, is_const: bool, ty: &str, name: &str, value: &str) -> String {
+        let binder = if is_const { "let" } else { "let mut" };
+        format!("  {binder} {name}: {ty} = {value} in\n")
+    }
+
+    fn emit_assignment(&self, name: &str, value: &str) -> String {
+        format!("  {name} <- {value};\n")
+    }
+
+    /// Lowers `while (cond) { body }` to a self-recursive auxiliary rule
+    /// rather than a loop construct: `rule loop$N() = match cond { True =>
+    /// { body loop$N() }, False => () }`, then an immediate call to kick it
+    /// off.
+    fn emit_while(&self, condition: &str, body: &str) -> String {
+        let rule = self.next_loop_rule();
+        format!(
+            "  rule {rule}() =\n    match {condition} {{\n      True => {{\n{body}        {rule}()\n      }},\n      False => (),\n    }};\n  {rule}()\n"
+        )
+    }
+
+    fn emit_if(&self, condition: &str, body: &str) -> String {
+        format!("  match {condition} {{\n    True => {{\n{body}    }},\n    False => (),\n  }}\n")
+    }
+
+    fn emit_return(&self, value: &str) -> String {
+        format!("  {value}\n")
+    }
+
+    fn emit_expression(&self, src: &str, node: &Node, types: &TypeInfo) -> String {
+        translate_ir_expression(src, node, types)
+    }
+}
+
+fn translate_ir_expression(src: &str, node: &Node, types: &TypeInfo) -> String {
+    match node.kind() {
+        "integer_literal" | "float_literal" => node.utf8_text(src.as_bytes()).unwrap().to_owned(),
+
+        "bool_literal" => match node.utf8_text(src.as_bytes()).unwrap() {
+            "true" => "True".to_owned(),
+            _ => "False".to_owned(),
+        },
+
+        "string_literal" => node.utf8_text(src.as_bytes()).unwrap().to_owned(),
+
+        "identifier" => node.utf8_text(src.as_bytes()).unwrap().to_owned(),
+
+        "unary_expression" => {
+            let operator = raw_operator(src, node);
+            let operand = node
+                .named_child(0)
+                .map(|child| translate_ir_expression(src, &child, types))
+                .unwrap_or_default();
+            format!("({operator} {operand})")
+        }
+
+        "binary_expression" => {
+            let mut cursor = node.walk();
+            let mut operands = node.named_children(&mut cursor);
+            let lhs_node = operands.next();
+            let rhs_node = operands.next();
+            let operand_type = lhs_node
+                .as_ref()
+                .map(|n| types.type_of(n))
+                .unwrap_or(Type::Unknown);
+            let operator = raw_operator(src, node);
+
+            let lhs = lhs_node
+                .map(|n| translate_ir_expression(src, &n, types))
+                .unwrap_or_default();
+            let rhs = rhs_node
+                .map(|n| translate_ir_expression(src, &n, types))
+                .unwrap_or_default();
+
+            if operator == "+" && operand_type == Type::Str {
+                // The IR has no built-in string concatenation operator;
+                // route it through an explicit rewrite rule instead.
+                format!("(Concat {lhs} {rhs})")
+            } else {
+                format!("({operator} {lhs} {rhs})")
+            }
+        }
+
+        "parenthesized_expression" => node
+            .named_child(0)
+            .map(|child| translate_ir_expression(src, &child, types))
+            .unwrap_or_default(),
+
+        "call_expression" => {
+            let call = CallExpression::cast(*node).expect("not a call_expression node");
+
+            let function_name = call
+                .function()
+                .map(|n| n.utf8_text(src.as_bytes()).unwrap().to_owned())
+                .unwrap_or_default();
+
+            let args = call
+                .arguments()
+                .map(|args_node| {
+                    let mut cursor = args_node.walk();
+                    args_node
+                        .named_children(&mut cursor)
+                        .map(|arg| translate_ir_expression(src, &arg, types))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .unwrap_or_default();
+
+            format!("({function_name} {args})")
+        }
+
+        _ => node.utf8_text(src.as_bytes()).unwrap().to_owned(),
+    }
+}
+
+/// The prefix-notation IR spells binary/unary operators the same as Miac;
+/// pull the anonymous operator token straight from the tree.
+fn raw_operator<'a>(src: &'a str, node: &Node) -> &'a str {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .find(|child| !child.is_named())
+        .map(|op| op.utf8_text(src.as_bytes()).unwrap())
+        .unwrap_or("")
+}
@@ -0,0 +1,303 @@
+//! Decouples the AST walk from emission so the same traversal can drive more
+//! than one target. Previously `translate_to_c`, `translate_function` and
+//! friends hardcoded C string formatting throughout; now they call into a
+//! [`Backend`] and only the backend knows what the output language looks
+//! like.
+
+pub mod c;
+pub mod ir;
+
+use tree_sitter::{Node, Tree};
+
+use crate::ast::{
+    AssignmentStatement, FunctionDefinition, IfStatement, SyntaxKind, VariableDeclaration,
+    WhileStatement,
+};
+use crate::diagnostics::{Diagnostic, Diagnostics};
+use crate::typecheck::TypeInfo;
+
+pub use c::CBackend;
+pub use ir::IrBackend;
+
+/// One method per construct the walk can hand off to emission. Everything
+/// here takes already-translated pieces (e.g. a function's already-rendered
+/// body) and arranges them in the target's syntax.
+pub trait Backend {
+    /// Maps a Miac type name (`i32`, `f32`, `string`, `bool`) to this
+    /// backend's spelling of it, or `None` if the backend doesn't recognize
+    /// the name (already reported by the type checker; the caller falls back
+    /// to an empty spelling rather than reporting it again).
+    fn translate_type(&self, miac_type: &str) -> Option<&'static str>;
+
+    /// Output emitted once, ahead of every translated item — e.g. runtime
+    /// helpers a target needs but Miac has no syntax to declare. Most
+    /// targets don't need one.
+    fn emit_prelude(&self) -> String {
+        String::new()
+    }
+
+    fn emit_function(
+        &self,
+        name: &str,
+        return_type: &str,
+        params: &[(String, String)],
+        body: &str,
+    ) -> String;
+
+    fn emit_variable(&self, is_const: bool, ty: &str, name: &str, value: &str) -> String;
+
+    fn emit_assignment(&self, name: &str, value: &str) -> String;
+
+    fn emit_while(&self, condition: &str, body: &str) -> String;
+
+    fn emit_if(&self, condition: &str, body: &str) -> String;
+
+    fn emit_return(&self, value: &str) -> String;
+
+    /// Recursively renders an expression node in this backend's syntax.
+    fn emit_expression(&self, src: &str, node: &Node, types: &TypeInfo) -> String;
+}
+
+/// `typecheck::resolve_named_type` already reports "unknown type" for this
+/// same node during the semantic pass that runs before translation; an
+/// unrecognized name here just falls back to an empty spelling instead of
+/// reporting it a second time.
+fn translate_type(backend: &dyn Backend, src: &str, node: &Node) -> String {
+    let text = node.utf8_text(src.as_bytes()).unwrap();
+    backend
+        .translate_type(text)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+/// Reports a "missing `field`" diagnostic and returns `None` when a required
+/// field is absent — an `ERROR`/`MISSING` recovery node left a hole the
+/// happy-path grammar never would — instead of letting the caller's
+/// `.unwrap()` panic on it.
+fn require<'tree>(
+    node: &Node,
+    field: Option<Node<'tree>>,
+    field_name: &str,
+    diags: &mut Diagnostics,
+) -> Option<Node<'tree>> {
+    if field.is_none() {
+        diags.push(Diagnostic::error(
+            format!("missing `{field_name}` in `{}`", node.kind()),
+            node.byte_range(),
+        ));
+    }
+    field
+}
+
+/// Translates a single top-level item (a function or variable declaration).
+/// Used by the one-shot path via [`translate_program`], and directly by the
+/// watch mode so it can re-translate one item at a time and cache the rest.
+pub fn translate_top_level_item(
+    src: &str,
+    node: &Node,
+    types: &TypeInfo,
+    backend: &dyn Backend,
+    diags: &mut Diagnostics,
+) -> String {
+    match SyntaxKind::from_kind(node.kind()) {
+        SyntaxKind::FunctionDefinition => {
+            translate_function(src, node, types, backend, diags).unwrap_or_default()
+        }
+        SyntaxKind::VariableDeclaration => {
+            translate_variable_declaration(src, node, types, backend, diags).unwrap_or_default()
+        }
+        _ => String::new(),
+    }
+}
+
+pub fn translate_program(
+    src: &str,
+    tree: &Tree,
+    types: &TypeInfo,
+    backend: &dyn Backend,
+    diags: &mut Diagnostics,
+) -> String {
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let mut out = backend.emit_prelude();
+
+    for child in root.children(&mut cursor) {
+        if check_error_or_missing(&child, diags) {
+            continue;
+        }
+
+        out.push_str(&translate_top_level_item(src, &child, types, backend, diags));
+    }
+
+    out
+}
+
+pub(crate) fn check_error_or_missing(node: &Node, diags: &mut Diagnostics) -> bool {
+    if node.is_missing() {
+        diags.push(Diagnostic::error(
+            format!("missing `{}`", node.kind()),
+            node.byte_range(),
+        ));
+        true
+    } else if node.is_error() {
+        diags.push(Diagnostic::error("syntax error", node.byte_range()));
+        true
+    } else {
+        false
+    }
+}
+
+fn translate_variable_declaration(
+    src: &str,
+    node: &Node,
+    types: &TypeInfo,
+    backend: &dyn Backend,
+    diags: &mut Diagnostics,
+) -> Option<String> {
+    let decl = VariableDeclaration::cast(*node).expect("not a variable_declaration node");
+
+    let type_node = require(node, decl.r#type(), "type", diags)?;
+    let name_node = require(node, decl.name(), "name", diags)?;
+    let value_node = require(node, decl.value(), "value", diags)?;
+
+    let var_type = translate_type(backend, src, &type_node);
+    let var_name = name_node.utf8_text(src.as_bytes()).unwrap();
+    let is_const = decl
+        .mutability_specifier()
+        .map(|n| n.utf8_text(src.as_bytes()).unwrap() == "const")
+        .unwrap_or(false);
+    let value_code = backend.emit_expression(src, &value_node, types);
+
+    Some(backend.emit_variable(is_const, &var_type, var_name, &value_code))
+}
+
+fn translate_function(
+    src: &str,
+    node: &Node,
+    types: &TypeInfo,
+    backend: &dyn Backend,
+    diags: &mut Diagnostics,
+) -> Option<String> {
+    let function = FunctionDefinition::cast(*node).expect("not a function_definition node");
+
+    let name_node = require(node, function.name(), "name", diags)?;
+    let return_type_node = require(node, function.return_type(), "return_type", diags)?;
+    let parameters_node = require(node, function.parameters(), "parameters", diags)?;
+    let body_node = require(node, function.body(), "body", diags)?;
+
+    let function_name = name_node.utf8_text(src.as_bytes()).unwrap();
+    let return_type = translate_type(backend, src, &return_type_node);
+    let parameters = crate::ast::Parameters(parameters_node);
+
+    let mut params = Vec::new();
+    for param in parameters.iter() {
+        let Some(name_node) = require(&parameters_node, param.name(), "name", diags) else {
+            continue;
+        };
+        let Some(type_node) = require(&parameters_node, param.r#type(), "type", diags) else {
+            continue;
+        };
+        let param_name = name_node.utf8_text(src.as_bytes()).unwrap().to_owned();
+        let param_type = translate_type(backend, src, &type_node);
+        params.push((param_type, param_name));
+    }
+
+    let body_code = translate_block(src, &body_node, types, backend, diags);
+
+    Some(backend.emit_function(function_name, &return_type, &params, &body_code))
+}
+
+const KNOWN_STATEMENT_KINDS: &[&str] = &[
+    "return_statement",
+    "variable_declaration",
+    "assignment_statement",
+    "while_statement",
+    "if_statement",
+];
+
+fn translate_block(
+    src: &str,
+    node: &Node,
+    types: &TypeInfo,
+    backend: &dyn Backend,
+    diags: &mut Diagnostics,
+) -> String {
+    let mut out = String::new();
+    let mut cursor = node.walk();
+
+    for statement in node.children(&mut cursor) {
+        if check_error_or_missing(&statement, diags) {
+            continue;
+        }
+
+        let statement_code = match SyntaxKind::from_kind(statement.kind()) {
+            SyntaxKind::ReturnStatement => {
+                match require(&statement, statement.child(1), "value", diags) {
+                    Some(value) => {
+                        let value_code = backend.emit_expression(src, &value, types);
+                        backend.emit_return(&value_code)
+                    }
+                    None => String::new(),
+                }
+            }
+            SyntaxKind::VariableDeclaration => {
+                translate_variable_declaration(src, &statement, types, backend, diags)
+                    .unwrap_or_default()
+            }
+            SyntaxKind::AssignmentStatement => {
+                let assignment =
+                    AssignmentStatement::cast(statement).expect("not an assignment_statement node");
+                let name_node = require(&statement, assignment.name(), "name", diags);
+                let value_node = require(&statement, assignment.value(), "value", diags);
+                match (name_node, value_node) {
+                    (Some(name_node), Some(value)) => {
+                        let name = name_node.utf8_text(src.as_bytes()).unwrap();
+                        let value_code = backend.emit_expression(src, &value, types);
+                        backend.emit_assignment(name, &value_code)
+                    }
+                    _ => String::new(),
+                }
+            }
+            SyntaxKind::WhileStatement => {
+                let while_stmt = WhileStatement::cast(statement).expect("not a while_statement node");
+                let condition_node = require(&statement, while_stmt.condition(), "condition", diags);
+                let body_node = require(&statement, while_stmt.body(), "body", diags);
+                match (condition_node, body_node) {
+                    (Some(condition), Some(body)) => {
+                        let condition_code = backend.emit_expression(src, &condition, types);
+                        let body_code = translate_block(src, &body, types, backend, diags);
+                        backend.emit_while(&condition_code, &body_code)
+                    }
+                    _ => String::new(),
+                }
+            }
+            SyntaxKind::IfStatement => {
+                let if_stmt = IfStatement::cast(statement).expect("not an if_statement node");
+                let condition_node = require(&statement, if_stmt.condition(), "condition", diags);
+                let body_node = require(&statement, if_stmt.body(), "body", diags);
+                match (condition_node, body_node) {
+                    (Some(condition), Some(body)) => {
+                        let condition_code = backend.emit_expression(src, &condition, types);
+                        let body_code = translate_block(src, &body, types, backend, diags);
+                        backend.emit_if(&condition_code, &body_code)
+                    }
+                    _ => String::new(),
+                }
+            }
+            _ => {
+                let other = statement.kind();
+                if statement.is_named() && !KNOWN_STATEMENT_KINDS.contains(&other) {
+                    diags.push(Diagnostic::error(
+                        format!("unknown statement kind `{other}`"),
+                        statement.byte_range(),
+                    ));
+                }
+                String::new()
+            }
+        };
+
+        out.push_str(&statement_code);
+    }
+
+    out
+}
@@ -0,0 +1,128 @@
+//! Source-span diagnostics for the transpiler.
+//!
+//! Instead of panicking or silently dropping malformed input, translation
+//! passes record a [`Diagnostic`] in a [`Diagnostics`] collector and carry on
+//! with a best-effort (possibly empty) translation. `main` prints every
+//! diagnostic with a caret-highlighted snippet and exits nonzero if any of
+//! them are errors.
+
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Range<usize>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Range<usize>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+/// Collects diagnostics produced while walking the tree so translation can
+/// keep going instead of aborting on the first problem.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Diagnostics::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    /// Renders every diagnostic against `src`, in the caret-under-token style
+    /// of compact error-highlighting crates, and prints it to stderr.
+    pub fn emit(&self, src: &str) {
+        for diagnostic in &self.diagnostics {
+            eprintln!("{}", render(src, diagnostic));
+        }
+    }
+}
+
+/// 1-based line/column of `offset` within `src`.
+fn line_col(src: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut col = 1;
+    for ch in src[..offset.min(src.len())].chars() {
+        if ch == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    (line, col)
+}
+
+fn line_text(src: &str, line: usize) -> &str {
+    src.lines().nth(line - 1).unwrap_or("")
+}
+
+fn render(src: &str, diagnostic: &Diagnostic) -> String {
+    const RED: &str = "\x1b[31m";
+    const BOLD: &str = "\x1b[1m";
+    const RESET: &str = "\x1b[0m";
+
+    let (line, col) = line_col(src, diagnostic.span.start);
+    let span_len = (diagnostic.span.end - diagnostic.span.start).max(1);
+    let text = line_text(src, line);
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "{BOLD}{RED}{}{RESET}{BOLD}: {}{RESET}\n",
+        diagnostic.severity, diagnostic.message
+    ));
+    out.push_str(&format!("  --> line {line}, column {col}\n"));
+    out.push_str(&format!("   | {text}\n"));
+    out.push_str(&format!(
+        "   | {}{RED}{}{RESET}\n",
+        " ".repeat(col.saturating_sub(1)),
+        "^".repeat(span_len)
+    ));
+    out
+}
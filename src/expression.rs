@@ -0,0 +1,121 @@
+//! Recursive expression translation.
+//!
+//! `translate_block` used to paste the original Miac source straight into
+//! the C output for assignments and conditions, which only worked because
+//! Miac and C happen to share surface syntax for those cases. This module
+//! walks expression nodes and rebuilds them as C, so the two languages are
+//! free to diverge (boolean literals, string handling, operators, call
+//! conventions, ...).
+
+use tree_sitter::Node;
+
+use crate::ast::CallExpression;
+use crate::typecheck::{Type, TypeInfo};
+
+/// Translates an expression node into C source, recursing into operands.
+pub fn translate_expression(src: &str, node: &Node, types: &TypeInfo) -> String {
+    match node.kind() {
+        "integer_literal" | "float_literal" => node.utf8_text(src.as_bytes()).unwrap().to_owned(),
+
+        "bool_literal" => match node.utf8_text(src.as_bytes()).unwrap() {
+            "true" => "1".to_owned(),
+            _ => "0".to_owned(),
+        },
+
+        "string_literal" => node.utf8_text(src.as_bytes()).unwrap().to_owned(),
+
+        "identifier" => node.utf8_text(src.as_bytes()).unwrap().to_owned(),
+
+        "unary_expression" => {
+            let operator = unary_operator(src, node);
+            let operand = node
+                .named_child(0)
+                .map(|child| translate_expression(src, &child, types))
+                .unwrap_or_default();
+            format!("{operator}{operand}")
+        }
+
+        "binary_expression" => {
+            let mut cursor = node.walk();
+            let mut operands = node.named_children(&mut cursor);
+            let lhs = operands.next();
+            let rhs = operands.next();
+            let operator = binary_operator(src, node);
+
+            let operand_type = lhs.as_ref().map(|n| types.type_of(n)).unwrap_or(Type::Unknown);
+
+            let lhs_code = lhs
+                .map(|n| translate_expression(src, &n, types))
+                .unwrap_or_default();
+            let rhs_code = rhs
+                .map(|n| translate_expression(src, &n, types))
+                .unwrap_or_default();
+
+            if operator == "+" && is_string(operand_type) {
+                // C has no `+` for `char *`; route through the `miac_concat`
+                // helper (see `CBackend::emit_prelude`) rather than
+                // `strcat`ing onto a `strdup`'d buffer with no room to grow.
+                format!("miac_concat({lhs_code}, {rhs_code})")
+            } else {
+                format!("{lhs_code} {operator} {rhs_code}")
+            }
+        }
+
+        "parenthesized_expression" => {
+            let inner = node
+                .named_child(0)
+                .map(|child| translate_expression(src, &child, types))
+                .unwrap_or_default();
+            format!("({inner})")
+        }
+
+        "call_expression" => translate_call(src, node, types),
+
+        _ => node.utf8_text(src.as_bytes()).unwrap().to_owned(),
+    }
+}
+
+fn translate_call(src: &str, node: &Node, types: &TypeInfo) -> String {
+    let call = CallExpression::cast(*node).expect("not a call_expression node");
+
+    let function_name = call
+        .function()
+        .map(|n| n.utf8_text(src.as_bytes()).unwrap().to_owned())
+        .unwrap_or_default();
+
+    let args = call
+        .arguments()
+        .map(|args_node| {
+            let mut cursor = args_node.walk();
+            args_node
+                .named_children(&mut cursor)
+                .map(|arg| translate_expression(src, &arg, types))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default();
+
+    format!("{function_name}({args})")
+}
+
+/// The grammar folds the operator into an anonymous (unnamed) token between
+/// the operands; extract it by scanning the node's raw children.
+fn binary_operator(src: &str, node: &Node) -> String {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .find(|child| !child.is_named())
+        .map(|op| op.utf8_text(src.as_bytes()).unwrap().to_owned())
+        .unwrap_or_default()
+}
+
+fn unary_operator(src: &str, node: &Node) -> String {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .find(|child| !child.is_named())
+        .map(|op| op.utf8_text(src.as_bytes()).unwrap().to_owned())
+        .unwrap_or_default()
+}
+
+fn is_string(ty: Type) -> bool {
+    ty == Type::Str
+}
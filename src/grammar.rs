@@ -0,0 +1,44 @@
+//! Loading the tree-sitter grammar at runtime instead of linking it in.
+//!
+//! `main` used to hardcode `tree_sitter_miac::language()`, baking the
+//! grammar into the binary and making it impossible to iterate on the
+//! grammar (or load a related dialect) without recompiling the transpiler.
+//! [`load`] optionally opens a compiled grammar shared library instead and
+//! pulls the `Language` out of it.
+
+use std::mem;
+
+use libloading::{Library, Symbol};
+use tree_sitter::Language;
+
+/// Symbol tree-sitter CLI emits by default for a grammar named `miac`.
+pub const DEFAULT_SYMBOL: &str = "tree_sitter_miac";
+
+/// Loads the grammar from a compiled shared library (`.so`/`.dylib`) at
+/// `path`, calling the `unsafe extern "C" fn() -> Language` exported as
+/// `symbol`.
+///
+/// The `Library` is intentionally leaked with `mem::forget`: the `Language`
+/// it hands back borrows static data owned by the library, so unloading it
+/// would leave that data dangling (and segfault the next time tree-sitter
+/// touches it).
+pub fn load_from_library(path: &str, symbol: &str) -> Result<Language, libloading::Error> {
+    unsafe {
+        let library = Library::new(path)?;
+        let constructor: Symbol<unsafe extern "C" fn() -> Language> =
+            library.get(symbol.as_bytes())?;
+        let language = constructor();
+        mem::forget(library);
+        Ok(language)
+    }
+}
+
+/// Loads `path`'s grammar if given, otherwise falls back to the grammar
+/// statically linked into this binary.
+pub fn load(path: Option<&str>, symbol: &str) -> Language {
+    match path {
+        Some(path) => load_from_library(path, symbol)
+            .unwrap_or_else(|e| panic!("failed to load grammar from {path:?}: {e}")),
+        None => tree_sitter_miac::language(),
+    }
+}
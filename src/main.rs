@@ -1,157 +1,115 @@
 #![allow(unused)]
-use std::fs::File;
 
-use tree_sitter::{Node, Parser, Tree};
-
-fn match_type(t: String) -> String {
-    let matched = match t.as_str() {
-        "i32" => "int",
-        "f32" => "float",
-        "string" => "char *",
-        "bool" => "int",
-        _ => "",
-    };
-
-    matched.to_owned()
+mod ast;
+mod backend;
+mod diagnostics;
+mod expression;
+mod grammar;
+mod typecheck;
+mod watch;
+
+use backend::{Backend, CBackend, IrBackend};
+use diagnostics::Diagnostics;
+use tree_sitter::Parser;
+
+enum Target {
+    C,
+    Ir,
 }
 
-fn get_field(src: &str, node: &Node, name: &str) -> String {
-    let child_node = node.child_by_field_name(name).unwrap();
-    child_node.utf8_text(src.as_bytes()).unwrap().to_string()
-}
-
-fn translate_to_c(src: &str, tree: &Tree) -> String {
-    let root = tree.root_node();
-    let mut cursor = root.walk();
-    let mut c_code = String::new();
-
-    for child in root.children(&mut cursor) {
-        match child.kind() {
-            "function_definition" => {
-                let translated_function = translate_function(src, &child);
-                c_code.push_str(&translated_function);
-            }
-
-            "variable_declaration" => {
-                let translated_variable = translate_variable_declaration(src, &child);
-                c_code.push_str(&translated_variable);
-            }
-            _ => {}
+impl Target {
+    fn parse(s: &str) -> Option<Target> {
+        match s {
+            "c" => Some(Target::C),
+            "ir" => Some(Target::Ir),
+            _ => None,
         }
     }
-
-    c_code
 }
 
-fn translate_variable_declaration(src: &str, node: &tree_sitter::Node) -> String {
-    let var_type = match_type(get_field(src, node, "type"));
-    let var_name = get_field(src, node, "name");
-    let mutability = match get_field(src, node, "mutability_specifier").as_str() {
-        "const" => "const ",
-        _ => ""
-    }.to_owned();
-
-    let var_value = node.child_by_field_name("value").unwrap();
-
-    let mut c_code = format!("{}{} {} = {};\n", mutability, var_type, var_name, var_value.utf8_text(src.as_bytes()).unwrap());
-
-    c_code
+struct Args {
+    in_file: String,
+    out_file: String,
+    target: Target,
+    grammar: Option<String>,
+    watch: bool,
 }
 
-fn translate_function(src: &str, node: &Node) -> String {
-    let function_name = get_field(src, node, "name");
-    let return_type = match_type(get_field(src, node, "return_type"));
-    let parameters = node.child_by_field_name("parameters").unwrap();
-    let body = node.child_by_field_name("body").unwrap();
-
-    let mut c_code = format!("{} {}(", return_type, function_name);
-
-
-    if parameters.child_count() > 0 {
-        let mut param_list = String::new();
-        let mut cursor = node.walk();
-        for child in parameters.children(&mut cursor) {
-            if child.kind() == "parameter" {
-                let param_name = get_field(src, &child, "name");
-                let param_type = match_type(get_field(src, &child, "type"));
-
-                param_list.push_str(&format!("{} {}, ", param_type, param_name));
-            }
+fn parse_args(raw: &[String]) -> Args {
+    let mut positional = Vec::new();
+    let mut target = Target::C;
+    let mut grammar = None;
+    let mut watch = false;
+
+    let mut iter = raw.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if let Some(value) = arg.strip_prefix("--target=") {
+            target = Target::parse(value).expect("--target must be `c` or `ir`");
+        } else if arg == "--target" {
+            let value = iter.next().expect("--target requires a value");
+            target = Target::parse(value).expect("--target must be `c` or `ir`");
+        } else if let Some(value) = arg.strip_prefix("--grammar=") {
+            grammar = Some(value.to_owned());
+        } else if arg == "--grammar" {
+            let value = iter.next().expect("--grammar requires a path");
+            grammar = Some(value.clone());
+        } else if arg == "--watch" {
+            watch = true;
+        } else {
+            positional.push(arg.clone());
         }
-        param_list.pop(); // Remove the trailing comma
-        param_list.pop(); // Remove the space
-        c_code.push_str(&param_list);
     }
 
-    c_code.push_str(") {\n");
-
-    let body_code = translate_block(src, &body);
-    c_code.push_str(&body_code);
-
-    c_code.push_str("}\n");
-
-    c_code
-}
-
-fn translate_block(src: &str, node: &tree_sitter::Node) -> String {
-    let mut c_code = String::new();
-    let mut cursor = node.walk();
-
-    for statement in node.children(&mut cursor) {
-        let statement_code = match statement.kind() {
-            "return_statement" => translate_return_statement(src, &statement),
-            "variable_declaration" => translate_variable_declaration(src, &statement),
-            "assignment_statement" => {
-                let text = statement.utf8_text(src.as_bytes()).unwrap().to_string();
-                format!("{text}\n")
-            }
-            "while_statement" => {
-                let condition = get_field(src, &statement, "condition");
-                let body = statement.child_by_field_name("body").unwrap();
-                let body_text = translate_block(src, &body);
-
-                format!("while ({condition}) {{\n{body_text}}}\n")
-            }
-            "if_statement" => {
-                let condition = get_field(src, &statement, "condition");
-                let body = statement.child_by_field_name("body").unwrap();
-                let body_text = translate_block(src, &body);
-
-                format!("if ({condition}) {{\n{body_text}}}\n")
-            }
-            _ => String::new(),
-        };
-
-        c_code.push_str(&statement_code);
+    assert!(
+        positional.len() == 2,
+        "usage: miac <input> <output> [--target=c|ir] [--grammar <path>] [--watch]"
+    );
+
+    Args {
+        in_file: positional[0].clone(),
+        out_file: positional[1].clone(),
+        target,
+        grammar,
+        watch,
     }
-
-    c_code
 }
 
-fn translate_return_statement(src: &str, node: &Node) -> String {
-    let child_node = node.child(1).unwrap();
-    let return_expr_code = child_node.utf8_text(src.as_bytes()).unwrap().to_string();
+fn main() -> std::io::Result<()> {
+    let raw_args: Vec<String> = std::env::args().collect();
+    let args = parse_args(&raw_args);
 
-    format!("return {};\n", return_expr_code)
-}
+    let language = grammar::load(args.grammar.as_deref(), grammar::DEFAULT_SYMBOL);
 
+    let backend: Box<dyn Backend> = match args.target {
+        Target::C => Box::new(CBackend),
+        Target::Ir => Box::new(IrBackend::default()),
+    };
 
-fn main() -> std::io::Result<()> {
-    let args: Vec<String> = std::env::args().collect();
-    let in_file = &args[1];
-    let out_file = &args[2];
+    if args.watch {
+        return watch::watch(&args.in_file, &args.out_file, language, backend.as_ref());
+    }
 
     let mut parser = Parser::new();
     parser
-        .set_language(tree_sitter_miac::language())
+        .set_language(language)
         .expect("Error loading Miac grammar");
 
-    let src = std::fs::read_to_string(in_file).expect("Failed to open input file");
+    let src = std::fs::read_to_string(&args.in_file).expect("Failed to open input file");
 
     let tree = parser.parse(src.clone(), None).unwrap();
 
-    let c_code = translate_to_c(&src, &tree);
-    std::fs::write(out_file, c_code);
+    let mut diags = Diagnostics::new();
+    let type_info = typecheck::check(&src, &tree.root_node(), &mut diags);
+
+    let code = backend::translate_program(&src, &tree, &type_info, backend.as_ref(), &mut diags);
+
+    if diags.has_errors() {
+        diags.emit(&src);
+        std::process::exit(1);
+    }
+    diags.emit(&src);
+
+    std::fs::write(&args.out_file, code)?;
 
     Ok(())
 }
@@ -0,0 +1,500 @@
+//! A semantic pass that builds nested scopes and function signatures, then
+//! type-checks assignments, `return` expressions, `while`/`if` conditions and
+//! call arguments against them.
+//!
+//! Mismatches are reported through [`crate::diagnostics`] rather than
+//! emitted as C; the resolved type of every expression node visited is kept
+//! around in [`TypeInfo::resolved`] so the backend can later pick the right
+//! C representation (and eventually format specifiers) without re-deriving
+//! it from scratch.
+
+use std::collections::HashMap;
+
+use tree_sitter::Node;
+
+use crate::ast::{FunctionDefinition, IfStatement, VariableDeclaration, WhileStatement};
+use crate::diagnostics::{Diagnostic, Diagnostics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Type {
+    I32,
+    F32,
+    Str,
+    Bool,
+    /// Could not be determined — already reported elsewhere, don't cascade.
+    Unknown,
+}
+
+impl Type {
+    pub fn from_miac_name(name: &str) -> Option<Type> {
+        match name {
+            "i32" => Some(Type::I32),
+            "f32" => Some(Type::F32),
+            "string" => Some(Type::Str),
+            "bool" => Some(Type::Bool),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::I32 => write!(f, "i32"),
+            Type::F32 => write!(f, "f32"),
+            Type::Str => write!(f, "string"),
+            Type::Bool => write!(f, "bool"),
+            Type::Unknown => write!(f, "<unknown>"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionSignature {
+    pub params: Vec<Type>,
+    pub return_type: Type,
+}
+
+/// A stack of lexical scopes, one pushed per `function_definition` and per
+/// nested block, holding each declared variable's type.
+#[derive(Debug, Default)]
+struct Scopes {
+    stack: Vec<HashMap<String, Type>>,
+}
+
+impl Scopes {
+    fn push(&mut self) {
+        self.stack.push(HashMap::new());
+    }
+
+    fn pop(&mut self) {
+        self.stack.pop();
+    }
+
+    fn declare(&mut self, name: &str, ty: Type) {
+        self.stack
+            .last_mut()
+            .expect("declare called outside any scope")
+            .insert(name.to_owned(), ty);
+    }
+
+    fn lookup(&self, name: &str) -> Option<Type> {
+        self.stack
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+    }
+}
+
+/// The result of the semantic pass: every function's signature and the
+/// resolved type of every expression node the checker visited.
+#[derive(Debug, Default)]
+pub struct TypeInfo {
+    pub functions: HashMap<String, FunctionSignature>,
+    pub resolved: HashMap<usize, Type>,
+}
+
+impl TypeInfo {
+    pub fn type_of(&self, node: &Node) -> Type {
+        self.resolved.get(&node.id()).copied().unwrap_or(Type::Unknown)
+    }
+}
+
+pub fn check(src: &str, root: &Node, diags: &mut Diagnostics) -> TypeInfo {
+    let mut info = TypeInfo::default();
+    collect_signatures(src, root, &mut info, diags);
+
+    let mut scopes = Scopes::default();
+    scopes.push();
+
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        match child.kind() {
+            "function_definition" => check_function(src, &child, &mut info, &mut scopes, diags),
+            "variable_declaration" => check_variable_declaration(src, &child, &mut info, &mut scopes, diags),
+            _ => {}
+        }
+    }
+
+    scopes.pop();
+    info
+}
+
+/// First pass over the root: record every function's name, parameter types
+/// and return type before checking any bodies, so forward calls resolve.
+fn collect_signatures(src: &str, root: &Node, info: &mut TypeInfo, diags: &mut Diagnostics) {
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if child.kind() != "function_definition" {
+            continue;
+        }
+        let Some(function) = FunctionDefinition::cast(child) else {
+            continue;
+        };
+        let Some(name_node) = function.name() else {
+            continue;
+        };
+        let name = name_node.utf8_text(src.as_bytes()).unwrap().to_owned();
+
+        let return_type = function
+            .return_type()
+            .and_then(|n| resolve_named_type(src, &n, diags));
+
+        let mut params = Vec::new();
+        if let Some(parameters) = function.parameters() {
+            let mut param_cursor = parameters.walk();
+            for param in parameters.children(&mut param_cursor) {
+                if param.kind() != "parameter" {
+                    continue;
+                }
+                let ty = param
+                    .child_by_field_name("type")
+                    .and_then(|n| resolve_named_type(src, &n, diags))
+                    .unwrap_or(Type::Unknown);
+                params.push(ty);
+            }
+        }
+
+        info.functions.insert(
+            name,
+            FunctionSignature {
+                params,
+                return_type: return_type.unwrap_or(Type::Unknown),
+            },
+        );
+    }
+}
+
+fn resolve_named_type(src: &str, node: &Node, diags: &mut Diagnostics) -> Option<Type> {
+    let text = node.utf8_text(src.as_bytes()).unwrap();
+    match Type::from_miac_name(text) {
+        Some(ty) => Some(ty),
+        None => {
+            diags.push(Diagnostic::error(
+                format!("unknown type `{text}`"),
+                node.byte_range(),
+            ));
+            None
+        }
+    }
+}
+
+fn check_function(
+    src: &str,
+    node: &Node,
+    info: &mut TypeInfo,
+    scopes: &mut Scopes,
+    diags: &mut Diagnostics,
+) {
+    let function = FunctionDefinition::cast(*node).expect("not a function_definition node");
+    // Parameter and return types were already resolved (and any unknown
+    // type already reported) while building the signature in
+    // `collect_signatures`; look them up instead of re-resolving them here,
+    // which would report the same "unknown type" diagnostic twice.
+    let signature = function
+        .name()
+        .and_then(|n| n.utf8_text(src.as_bytes()).ok())
+        .and_then(|name| info.functions.get(name))
+        .cloned();
+
+    let return_type = signature
+        .as_ref()
+        .map(|sig| sig.return_type)
+        .unwrap_or(Type::Unknown);
+
+    scopes.push();
+
+    if let Some(parameters) = function.parameters() {
+        let mut cursor = parameters.walk();
+        let mut param_types = signature
+            .as_ref()
+            .map(|sig| sig.params.as_slice())
+            .unwrap_or(&[])
+            .iter();
+        for param in parameters.children(&mut cursor) {
+            if param.kind() != "parameter" {
+                continue;
+            }
+            let Some(name_node) = param.child_by_field_name("name") else {
+                continue;
+            };
+            let ty = param_types.next().copied().unwrap_or(Type::Unknown);
+            scopes.declare(name_node.utf8_text(src.as_bytes()).unwrap(), ty);
+        }
+    }
+
+    if let Some(body) = function.body() {
+        check_block(src, &body, return_type, info, scopes, diags);
+    }
+
+    scopes.pop();
+}
+
+fn check_variable_declaration(
+    src: &str,
+    node: &Node,
+    info: &mut TypeInfo,
+    scopes: &mut Scopes,
+    diags: &mut Diagnostics,
+) {
+    let decl = VariableDeclaration::cast(*node).expect("not a variable_declaration node");
+    let Some(name_node) = decl.name() else { return };
+    let name = name_node.utf8_text(src.as_bytes()).unwrap();
+
+    let declared_type = decl
+        .r#type()
+        .and_then(|n| resolve_named_type(src, &n, diags))
+        .unwrap_or(Type::Unknown);
+
+    if let Some(value) = decl.value() {
+        let value_type = check_expression(src, &value, info, scopes, diags);
+        if declared_type != Type::Unknown
+            && value_type != Type::Unknown
+            && declared_type != value_type
+        {
+            diags.push(Diagnostic::error(
+                format!(
+                    "expected `{declared_type}`, found `{value_type}` in initializer of `{name}`"
+                ),
+                value.byte_range(),
+            ));
+        }
+    }
+
+    scopes.declare(name, declared_type);
+}
+
+fn check_block(
+    src: &str,
+    node: &Node,
+    return_type: Type,
+    info: &mut TypeInfo,
+    scopes: &mut Scopes,
+    diags: &mut Diagnostics,
+) {
+    scopes.push();
+
+    let mut cursor = node.walk();
+    for statement in node.children(&mut cursor) {
+        match statement.kind() {
+            "variable_declaration" => {
+                check_variable_declaration(src, &statement, info, scopes, diags)
+            }
+            "assignment_statement" => {
+                check_assignment(src, &statement, info, scopes, diags);
+            }
+            "return_statement" => {
+                if let Some(expr) = statement.child(1) {
+                    let found = check_expression(src, &expr, info, scopes, diags);
+                    if return_type != Type::Unknown && found != Type::Unknown && found != return_type {
+                        diags.push(Diagnostic::error(
+                            format!("expected return type `{return_type}`, found `{found}`"),
+                            expr.byte_range(),
+                        ));
+                    }
+                }
+            }
+            "while_statement" => {
+                let Some(stmt) = WhileStatement::cast(statement) else {
+                    continue;
+                };
+                if let Some(condition) = stmt.condition() {
+                    check_condition(src, &condition, info, scopes, diags);
+                }
+                if let Some(body) = stmt.body() {
+                    check_block(src, &body, return_type, info, scopes, diags);
+                }
+            }
+            "if_statement" => {
+                let Some(stmt) = IfStatement::cast(statement) else {
+                    continue;
+                };
+                if let Some(condition) = stmt.condition() {
+                    check_condition(src, &condition, info, scopes, diags);
+                }
+                if let Some(body) = stmt.body() {
+                    check_block(src, &body, return_type, info, scopes, diags);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    scopes.pop();
+}
+
+fn check_condition(
+    src: &str,
+    node: &Node,
+    info: &mut TypeInfo,
+    scopes: &mut Scopes,
+    diags: &mut Diagnostics,
+) {
+    let ty = check_expression(src, node, info, scopes, diags);
+    if ty != Type::Unknown && ty != Type::Bool {
+        diags.push(Diagnostic::error(
+            format!("expected `bool` condition, found `{ty}`"),
+            node.byte_range(),
+        ));
+    }
+}
+
+fn check_assignment(
+    src: &str,
+    node: &Node,
+    info: &mut TypeInfo,
+    scopes: &mut Scopes,
+    diags: &mut Diagnostics,
+) {
+    let Some(name_node) = node.child_by_field_name("name") else {
+        return;
+    };
+    let Some(value) = node.child_by_field_name("value") else {
+        return;
+    };
+
+    let name = name_node.utf8_text(src.as_bytes()).unwrap();
+    let value_type = check_expression(src, &value, info, scopes, diags);
+
+    match scopes.lookup(name) {
+        Some(declared_type) => {
+            if declared_type != Type::Unknown
+                && value_type != Type::Unknown
+                && declared_type != value_type
+            {
+                diags.push(Diagnostic::error(
+                    format!("cannot assign `{value_type}` to `{name}` of type `{declared_type}`"),
+                    value.byte_range(),
+                ));
+            }
+        }
+        None => diags.push(Diagnostic::error(
+            format!("use of undeclared variable `{name}`"),
+            name_node.byte_range(),
+        )),
+    }
+}
+
+/// Resolves the type of an expression node, recording it in `info.resolved`
+/// so later passes don't need to walk the expression again.
+fn check_expression(
+    src: &str,
+    node: &Node,
+    info: &mut TypeInfo,
+    scopes: &mut Scopes,
+    diags: &mut Diagnostics,
+) -> Type {
+    let ty = match node.kind() {
+        "integer_literal" => Type::I32,
+        "float_literal" => Type::F32,
+        "string_literal" => Type::Str,
+        "bool_literal" => Type::Bool,
+        "identifier" => {
+            let name = node.utf8_text(src.as_bytes()).unwrap();
+            match scopes.lookup(name) {
+                Some(ty) => ty,
+                None => {
+                    diags.push(Diagnostic::error(
+                        format!("use of undeclared variable `{name}`"),
+                        node.byte_range(),
+                    ));
+                    Type::Unknown
+                }
+            }
+        }
+        "call_expression" => check_call(src, node, info, scopes, diags),
+        "binary_expression" => {
+            let mut cursor = node.walk();
+            let operands: Vec<Type> = node
+                .named_children(&mut cursor)
+                .map(|child| check_expression(src, &child, info, scopes, diags))
+                .collect();
+            let operand_type = operands.into_iter().find(|t| *t != Type::Unknown).unwrap_or(Type::Unknown);
+
+            match binary_operator(src, node) {
+                "==" | "!=" | "<" | ">" | "<=" | ">=" | "&&" | "||" => Type::Bool,
+                _ => operand_type,
+            }
+        }
+        "unary_expression" | "parenthesized_expression" => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .next()
+                .map(|child| check_expression(src, &child, info, scopes, diags))
+                .unwrap_or(Type::Unknown)
+        }
+        _ => Type::Unknown,
+    };
+
+    info.resolved.insert(node.id(), ty);
+    ty
+}
+
+/// The grammar folds the operator into an anonymous (unnamed) token between
+/// the operands; extract it by scanning the node's raw children.
+fn binary_operator<'a>(src: &'a str, node: &Node) -> &'a str {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .find(|child| !child.is_named())
+        .map(|op| op.utf8_text(src.as_bytes()).unwrap())
+        .unwrap_or("")
+}
+
+fn check_call(
+    src: &str,
+    node: &Node,
+    info: &mut TypeInfo,
+    scopes: &mut Scopes,
+    diags: &mut Diagnostics,
+) -> Type {
+    let Some(name_node) = node.child_by_field_name("function") else {
+        return Type::Unknown;
+    };
+    let name = name_node.utf8_text(src.as_bytes()).unwrap().to_owned();
+
+    let args: Vec<Node> = node
+        .child_by_field_name("arguments")
+        .map(|args_node| {
+            let mut cursor = args_node.walk();
+            args_node.named_children(&mut cursor).collect()
+        })
+        .unwrap_or_default();
+
+    let arg_types: Vec<Type> = args
+        .iter()
+        .map(|arg| check_expression(src, arg, info, scopes, diags))
+        .collect();
+
+    let Some(signature) = info.functions.get(&name).cloned() else {
+        diags.push(Diagnostic::error(
+            format!("call to undeclared function `{name}`"),
+            name_node.byte_range(),
+        ));
+        return Type::Unknown;
+    };
+
+    if signature.params.len() != arg_types.len() {
+        diags.push(Diagnostic::error(
+            format!(
+                "`{name}` expects {} argument(s), found {}",
+                signature.params.len(),
+                arg_types.len()
+            ),
+            node.byte_range(),
+        ));
+    } else {
+        for (arg, (expected, found)) in args
+            .iter()
+            .zip(signature.params.iter().zip(arg_types.iter()))
+        {
+            if *expected != Type::Unknown && *found != Type::Unknown && expected != found {
+                diags.push(Diagnostic::error(
+                    format!("expected `{expected}`, found `{found}`"),
+                    arg.byte_range(),
+                ));
+            }
+        }
+    }
+
+    signature.return_type
+}
@@ -0,0 +1,213 @@
+//! `--watch` mode: keep the previous [`Tree`] around, and on each change to
+//! the input file reuse tree-sitter's edit API instead of reparsing (and
+//! re-translating) the whole file from scratch.
+//!
+//! Only the top-level items (functions, variable declarations) whose byte
+//! ranges overlap `Tree::changed_ranges` are re-translated; everything
+//! else reuses its last emitted text. Items are matched across edits by
+//! name rather than by position, since an edit earlier in the file shifts
+//! every later byte range even when the item itself is untouched.
+
+use std::collections::HashMap;
+use std::io;
+use std::ops::Range;
+use std::time::Duration;
+
+use tree_sitter::{InputEdit, Language, Node, Parser, Point, Tree};
+
+use crate::backend::{self, Backend};
+use crate::diagnostics::Diagnostics;
+use crate::typecheck;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+struct CachedItem {
+    code: String,
+}
+
+pub fn watch(in_file: &str, out_file: &str, language: Language, backend: &dyn Backend) -> io::Result<()> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(language)
+        .expect("Error loading Miac grammar");
+
+    let mut src = std::fs::read_to_string(in_file)?;
+    let mut tree = parser
+        .parse(&src, None)
+        .expect("initial parse produced no tree");
+
+    let mut cache: HashMap<String, CachedItem> = HashMap::new();
+    std::fs::write(out_file, retranslate_all(&src, &tree, backend, &mut cache))?;
+
+    let mut last_modified = std::fs::metadata(in_file)?.modified()?;
+
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        let Ok(modified) = std::fs::metadata(in_file).and_then(|m| m.modified()) else {
+            continue;
+        };
+        if modified == last_modified {
+            continue;
+        }
+        last_modified = modified;
+
+        let Ok(new_src) = std::fs::read_to_string(in_file) else {
+            continue;
+        };
+        if new_src == src {
+            continue;
+        }
+
+        let edit = compute_edit(&src, &new_src);
+        tree.edit(&edit);
+
+        let new_tree = parser
+            .parse(&new_src, Some(&tree))
+            .expect("incremental parse produced no tree");
+        let changed: Vec<Range<usize>> = tree
+            .changed_ranges(&new_tree)
+            .map(|r| r.start_byte..r.end_byte)
+            .collect();
+
+        let code = retranslate_changed(&new_src, &new_tree, &changed, backend, &mut cache);
+        std::fs::write(out_file, code)?;
+
+        src = new_src;
+        tree = new_tree;
+    }
+}
+
+fn item_key(src: &str, node: &Node) -> String {
+    let name = node
+        .child_by_field_name("name")
+        .and_then(|n| n.utf8_text(src.as_bytes()).ok());
+    match name {
+        Some(name) => format!("{}:{name}", node.kind()),
+        None => format!("{}:{}", node.kind(), node.byte_range().start),
+    }
+}
+
+fn is_top_level_item(kind: &str) -> bool {
+    matches!(kind, "function_definition" | "variable_declaration")
+}
+
+fn retranslate_all(
+    src: &str,
+    tree: &Tree,
+    backend: &dyn Backend,
+    cache: &mut HashMap<String, CachedItem>,
+) -> String {
+    let mut diags = Diagnostics::new();
+    let types = typecheck::check(src, &tree.root_node(), &mut diags);
+
+    cache.clear();
+    let mut out = backend.emit_prelude();
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if !is_top_level_item(child.kind()) {
+            backend::check_error_or_missing(&child, &mut diags);
+            continue;
+        }
+        let code = backend::translate_top_level_item(src, &child, &types, backend, &mut diags);
+        cache.insert(item_key(src, &child), CachedItem { code: code.clone() });
+        out.push_str(&code);
+    }
+
+    diags.emit(src);
+    out
+}
+
+fn retranslate_changed(
+    src: &str,
+    tree: &Tree,
+    changed_ranges: &[Range<usize>],
+    backend: &dyn Backend,
+    cache: &mut HashMap<String, CachedItem>,
+) -> String {
+    let mut diags = Diagnostics::new();
+    let types = typecheck::check(src, &tree.root_node(), &mut diags);
+
+    let mut new_cache = HashMap::new();
+    let mut out = backend.emit_prelude();
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if !is_top_level_item(child.kind()) {
+            backend::check_error_or_missing(&child, &mut diags);
+            continue;
+        }
+
+        let key = item_key(src, &child);
+        let range = child.byte_range();
+        let touched = changed_ranges.iter().any(|r| ranges_overlap(r, &range));
+
+        let code = if touched {
+            backend::translate_top_level_item(src, &child, &types, backend, &mut diags)
+        } else {
+            match cache.get(&key) {
+                Some(cached) => cached.code.clone(),
+                None => backend::translate_top_level_item(src, &child, &types, backend, &mut diags),
+            }
+        };
+
+        new_cache.insert(key, CachedItem { code: code.clone() });
+        out.push_str(&code);
+    }
+
+    *cache = new_cache;
+    diags.emit(src);
+    out
+}
+
+fn ranges_overlap(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Builds the `InputEdit` tree-sitter needs from the common prefix/suffix of
+/// `old` and `new` — the smallest single edit that turns one into the
+/// other, which is all `Tree::edit` needs to know what to invalidate.
+fn compute_edit(old: &str, new: &str) -> InputEdit {
+    let old_bytes = old.as_bytes();
+    let new_bytes = new.as_bytes();
+
+    let mut prefix = 0;
+    while prefix < old_bytes.len()
+        && prefix < new_bytes.len()
+        && old_bytes[prefix] == new_bytes[prefix]
+    {
+        prefix += 1;
+    }
+
+    let mut old_end = old_bytes.len();
+    let mut new_end = new_bytes.len();
+    while old_end > prefix && new_end > prefix && old_bytes[old_end - 1] == new_bytes[new_end - 1] {
+        old_end -= 1;
+        new_end -= 1;
+    }
+
+    InputEdit {
+        start_byte: prefix,
+        old_end_byte: old_end,
+        new_end_byte: new_end,
+        start_position: point_at(old, prefix),
+        old_end_position: point_at(old, old_end),
+        new_end_position: point_at(new, new_end),
+    }
+}
+
+fn point_at(src: &str, byte_offset: usize) -> Point {
+    let mut row = 0;
+    let mut line_start = 0;
+    for (i, b) in src.as_bytes().iter().enumerate() {
+        if i >= byte_offset {
+            break;
+        }
+        if *b == b'\n' {
+            row += 1;
+            line_start = i + 1;
+        }
+    }
+    Point::new(row, byte_offset - line_start)
+}